@@ -1,17 +1,25 @@
 use crate::api::AppState;
+use crate::metrics;
 use serde_json::json;
+use std::time::Instant;
 
 pub async fn notify_owner(id: &str, _st: &AppState) -> anyhow::Result<()> {
     if let Ok(hook) = std::env::var("SLACK_WEBHOOK_URL") {
-        let _ = reqwest::Client::new().post(&hook)
-            .json(&json!({"text": format!("SurveiLens alert {}", id)})).send().await?;
+        let started = Instant::now();
+        let res = reqwest::Client::new().post(&hook)
+            .json(&json!({"text": format!("SurveiLens alert {}", id)})).send().await;
+        metrics::record_latency("slack", started);
+        let _ = res?;
     }
     Ok(())
 }
 pub async fn notify_responder(id: &str, _st: &AppState) -> anyhow::Result<()> {
     if let Ok(hook) = std::env::var("SLACK_WEBHOOK_URL_RESPONDER") {
-        let _ = reqwest::Client::new().post(&hook)
-            .json(&json!({"text": format!("Responder escalation {}", id)})).send().await?;
+        let started = Instant::now();
+        let res = reqwest::Client::new().post(&hook)
+            .json(&json!({"text": format!("Responder escalation {}", id)})).send().await;
+        metrics::record_latency("slack", started);
+        let _ = res?;
     }
     Ok(())
 }
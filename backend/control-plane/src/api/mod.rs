@@ -1,10 +1,44 @@
+use std::convert::Infallible;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
 use axum::{
-    extract::{Path, Query},
+    extract::{Path, Query, State},
     http::StatusCode,
-    Json,
+    response::sse::{Event, KeepAlive, Sse},
+    Extension, Json,
 };
+use dashmap::DashMap;
+use futures::{Stream, StreamExt};
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
+use tokio::sync::broadcast;
+use tokio_stream::wrappers::BroadcastStream;
+
+use crate::auth::Ticket;
+use crate::metrics;
+
+const ALERT_EVENTS_CAPACITY: usize = 1024;
+
+#[derive(Clone)]
+pub struct AppState {
+    pub alerts: Arc<DashMap<String, Value>>,
+    pub last_quiet: Arc<DashMap<String, u64>>,
+    pub store: crate::store::DynStore,
+    pub alert_events: broadcast::Sender<Value>,
+}
+
+impl AppState {
+    pub async fn from_env() -> anyhow::Result<Self> {
+        let (alert_events, _rx) = broadcast::channel(ALERT_EVENTS_CAPACITY);
+        Ok(Self {
+            alerts: Arc::new(DashMap::new()),
+            last_quiet: Arc::new(DashMap::new()),
+            store: crate::store::from_env().await?,
+            alert_events,
+        })
+    }
+}
 
 #[derive(Serialize)]
 pub struct OkResp { pub ok: bool }
@@ -13,26 +47,54 @@ pub struct OkResp { pub ok: bool }
 pub struct StartQuery { pub rtsp: Option<String> }
 
 pub async fn post_start(
+    Extension(ticket): Extension<Ticket>,
     Path((site, cam)): Path<(String, String)>,
     Query(q): Query<StartQuery>,
+) -> Result<Json<Value>, StatusCode> {
+    crate::auth::require_scope(&ticket, "camera:control")?;
+    let result = post_start_inner(&site, &cam, q).await;
+    metrics::track_request("cameras", "start", if result.is_ok() { "ok" } else { "error" });
+    result
+}
+
+async fn post_start_inner(
+    site: &str,
+    cam: &str,
+    q: StartQuery,
 ) -> Result<Json<Value>, StatusCode> {
     let rtsp = q.rtsp
         .or_else(|| std::env::var("DEFAULT_RTSP").ok())
         .ok_or(StatusCode::BAD_REQUEST)?;
 
-    let ack = crate::grpc_client::send_start(&site, &cam, &rtsp)
-        .await
-        .map_err(|_| StatusCode::BAD_GATEWAY)?;
+    let started = Instant::now();
+    let ack = crate::grpc_client::send_start(site, cam, &rtsp).await;
+    metrics::record_latency("grpc", started);
+    let ack = ack.map_err(|_| StatusCode::BAD_GATEWAY)?;
+    if ack.ok {
+        metrics::camera_started();
+    }
 
     Ok(Json(json!({ "ok": ack.ok, "msg": ack.msg })))
 }
 
 pub async fn post_stop(
+    Extension(ticket): Extension<Ticket>,
     Path((site, cam)): Path<(String, String)>
 ) -> Result<Json<OkResp>, StatusCode> {
-    let _ack = crate::grpc_client::send_stop(&site, &cam)
-        .await
-        .map_err(|_| StatusCode::BAD_GATEWAY)?;
+    crate::auth::require_scope(&ticket, "camera:control")?;
+    let result = post_stop_inner(&site, &cam).await;
+    metrics::track_request("cameras", "stop", if result.is_ok() { "ok" } else { "error" });
+    result
+}
+
+async fn post_stop_inner(site: &str, cam: &str) -> Result<Json<OkResp>, StatusCode> {
+    let started = Instant::now();
+    let ack = crate::grpc_client::send_stop(site, cam).await;
+    metrics::record_latency("grpc", started);
+    let ack = ack.map_err(|_| StatusCode::BAD_GATEWAY)?;
+    if ack.ok {
+        metrics::camera_stopped();
+    }
     Ok(Json(OkResp { ok: true }))
 }
 
@@ -45,14 +107,47 @@ pub struct PolicyCompileBody {
 }
 
 pub async fn post_policy_compile(
+    Extension(ticket): Extension<Ticket>,
     Json(body): Json<PolicyCompileBody>
 ) -> Result<Json<OkResp>, StatusCode> {
-    crate::policy::compile_and_push(&body.site_id, &body.camera_id, body.params)
+    crate::auth::require_scope(&ticket, "policy:write")?;
+    let result = crate::policy::compile_and_push(&body.site_id, &body.camera_id, body.params)
         .await
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR);
+    metrics::track_request("policy", "compile", if result.is_ok() { "ok" } else { "error" });
+    result?;
     Ok(Json(OkResp { ok: true }))
 }
 
 pub async fn get_health() -> Json<Value> {
     Json(json!({ "ok": true, "service": "control-plane" }))
 }
+
+pub async fn get_stream(
+    Extension(ticket): Extension<Ticket>,
+    State(st): State<AppState>,
+    Path((site, cam)): Path<(String, String)>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, StatusCode> {
+    crate::auth::require_scope(&ticket, "alerts:read")?;
+    let rx = st.alert_events.subscribe();
+    let stream = BroadcastStream::new(rx).filter_map(move |msg| {
+        let site = site.clone();
+        let cam = cam.clone();
+        async move {
+            let alert = msg.ok()?;
+            if alert["site_id"].as_str() != Some(site.as_str())
+                || alert["camera_id"].as_str() != Some(cam.as_str())
+            {
+                return None;
+            }
+            let data = serde_json::to_string(&alert).ok()?;
+            Some(Ok(Event::default().data(data)))
+        }
+    });
+
+    Ok(Sse::new(stream).keep_alive(
+        KeepAlive::new()
+            .interval(Duration::from_secs(15))
+            .text("keep-alive"),
+    ))
+}
@@ -0,0 +1,143 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use axum::{
+    extract::Request,
+    http::{header, StatusCode},
+    middleware::Next,
+    response::Response,
+    Json,
+};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+type HmacSha256 = Hmac<Sha256>;
+
+const TICKET_TTL_SECS: u64 = 3600;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Ticket {
+    pub subject: String,
+    pub scopes: Vec<String>,
+    pub issued_at: u64,
+    pub expiry: u64,
+}
+
+impl Ticket {
+    pub fn has_scope(&self, scope: &str) -> bool {
+        self.scopes.iter().any(|s| s == scope)
+    }
+}
+
+#[derive(Deserialize)]
+pub struct LoginBody {
+    pub subject: String,
+    pub secret: String,
+    #[serde(default)]
+    pub scopes: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub struct LoginResp {
+    pub token: String,
+    pub expiry: u64,
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+}
+
+fn signing_key() -> anyhow::Result<String> {
+    std::env::var("SURVEILENS_TICKET_SIGNING_KEY")
+        .map_err(|_| anyhow::anyhow!("SURVEILENS_TICKET_SIGNING_KEY not set"))
+}
+
+fn operator_secret() -> anyhow::Result<String> {
+    std::env::var("SURVEILENS_OPERATOR_SECRET")
+        .map_err(|_| anyhow::anyhow!("SURVEILENS_OPERATOR_SECRET not set"))
+}
+
+// Fixed subject -> allowed-scopes policy so a responder account can't just ask for
+// policy:write and get it; the operator secret alone only proves a legitimate client.
+fn allowed_scopes(subject: &str) -> anyhow::Result<Vec<String>> {
+    let raw = std::env::var("SURVEILENS_SUBJECT_SCOPES")
+        .map_err(|_| anyhow::anyhow!("SURVEILENS_SUBJECT_SCOPES not set"))?;
+    let policy: std::collections::HashMap<String, Vec<String>> = serde_json::from_str(&raw)?;
+    Ok(policy.get(subject).cloned().unwrap_or_default())
+}
+
+fn sign(payload_b64: &str) -> anyhow::Result<String> {
+    let key = signing_key()?;
+    let mut mac = HmacSha256::new_from_slice(key.as_bytes())?;
+    mac.update(payload_b64.as_bytes());
+    Ok(URL_SAFE_NO_PAD.encode(mac.finalize().into_bytes()))
+}
+
+fn encode(ticket: &Ticket) -> anyhow::Result<String> {
+    let payload_b64 = URL_SAFE_NO_PAD.encode(serde_json::to_vec(ticket)?);
+    let sig = sign(&payload_b64)?;
+    Ok(format!("{payload_b64}.{sig}"))
+}
+
+fn decode(token: &str) -> anyhow::Result<Ticket> {
+    let (payload_b64, sig) = token
+        .split_once('.')
+        .ok_or_else(|| anyhow::anyhow!("malformed ticket"))?;
+    let expected = sign(payload_b64)?;
+    if !ct_eq(expected.as_bytes(), sig.as_bytes()) {
+        return Err(anyhow::anyhow!("ticket signature mismatch"));
+    }
+    let ticket: Ticket = serde_json::from_slice(&URL_SAFE_NO_PAD.decode(payload_b64)?)?;
+    if ticket.expiry < now_secs() {
+        return Err(anyhow::anyhow!("ticket expired"));
+    }
+    Ok(ticket)
+}
+
+fn ct_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+pub async fn post_login(Json(body): Json<LoginBody>) -> Result<Json<LoginResp>, StatusCode> {
+    let expected = operator_secret().map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    if !ct_eq(expected.as_bytes(), body.secret.as_bytes()) {
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+    let allowed = allowed_scopes(&body.subject).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let scopes: Vec<String> = body.scopes.into_iter().filter(|s| allowed.contains(s)).collect();
+
+    let issued_at = now_secs();
+    let ticket = Ticket {
+        subject: body.subject,
+        scopes,
+        issued_at,
+        expiry: issued_at + TICKET_TTL_SECS,
+    };
+    let token = encode(&ticket).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    Ok(Json(LoginResp { token, expiry: ticket.expiry }))
+}
+
+pub async fn require_ticket(mut req: Request, next: Next) -> Result<Response, StatusCode> {
+    let token = req
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+
+    let ticket = decode(token).map_err(|_| StatusCode::UNAUTHORIZED)?;
+    req.extensions_mut().insert(ticket);
+    Ok(next.run(req).await)
+}
+
+pub fn require_scope(ticket: &Ticket, scope: &str) -> Result<(), StatusCode> {
+    if ticket.has_scope(scope) {
+        Ok(())
+    } else {
+        Err(StatusCode::FORBIDDEN)
+    }
+}
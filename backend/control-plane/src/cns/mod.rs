@@ -1,25 +1,33 @@
 use crate::api::AppState;
+use crate::metrics;
+use anyhow::{anyhow, Result};
 use serde_json::{json, Value};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
-pub fn enrich_async(alert: Value, st: AppState) {
-    tokio::spawn(async move {
-        if let Ok(ns_url) = std::env::var("NEURALSEEK_ENDPOINT") {
-            let redacted = build_redacted(&alert);
-            if let Ok(resp) = reqwest::Client::new()
-                .post(ns_url).bearer_auth(std::env::var("NEURALSEEK_API_KEY").unwrap_or_default())
-                .json(&redacted).timeout(Duration::from_secs(5))
-                .send().await {
-                if let Ok(mut cns) = resp.json::<Value>().await {
-                    let id = alert["id"].as_str().unwrap_or_default().to_string();
-                    let v = st.alerts.get(&id).map(|x| x.value().clone()).unwrap_or(alert.clone());
-                    let mut v2 = v.clone();
-                    v2["cns"] = cns.take();
-                    st.alerts.insert(id.clone(), v2.clone());
-                }
-            }
-        }
-    });
+/// Round-trips `alert` through NeuralSeek and merges the response into
+/// `st.alerts` under `cns`. Returns `Err` on any failure (missing config,
+/// request error, non-JSON body) so the caller's job queue can retry it.
+pub async fn enrich(alert: Value, st: &AppState) -> Result<()> {
+    let ns_url = std::env::var("NEURALSEEK_ENDPOINT")
+        .map_err(|_| anyhow!("NEURALSEEK_ENDPOINT not configured"))?;
+    let redacted = build_redacted(&alert);
+    let started = Instant::now();
+    let sent = reqwest::Client::new()
+        .post(ns_url).bearer_auth(std::env::var("NEURALSEEK_API_KEY").unwrap_or_default())
+        .json(&redacted).timeout(Duration::from_secs(5))
+        .send().await;
+    metrics::record_latency("neuralseek", started);
+
+    let mut cns = sent?.error_for_status()?.json::<Value>().await?;
+
+    let id = alert["id"].as_str().unwrap_or_default().to_string();
+    let v = st.alerts.get(&id).map(|x| x.value().clone()).unwrap_or(alert.clone());
+    let mut v2 = v.clone();
+    v2["cns"] = cns.take();
+    st.alerts.insert(id, v2.clone());
+    metrics::set_alert_count(st.alerts.len() as f64);
+    let _ = st.alert_events.send(v2);
+    Ok(())
 }
 
 fn build_redacted(a: &Value) -> Value {
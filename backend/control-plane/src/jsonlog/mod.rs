@@ -1,8 +1,6 @@
 use crate::api::AppState;
 use serde_json::json;
-use std::{fs, path::PathBuf, time::{SystemTime, UNIX_EPOCH}};
-
-fn root() -> PathBuf { PathBuf::from("surveilens/backend/data/jsonlogs") }
+use std::time::{SystemTime, UNIX_EPOCH};
 
 pub async fn maybe_emit(key: &str, rec: &serde_json::Value, st: &AppState) -> anyhow::Result<()> {
     let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
@@ -10,51 +8,58 @@ pub async fn maybe_emit(key: &str, rec: &serde_json::Value, st: &AppState) -> an
 
     let level = rec["level_local"].as_str().unwrap_or("none");
     if level=="medium" || level=="high" {
+        let alert_id = rec["id"].as_str().unwrap_or_default().to_string();
         let out = json!({
-          "ts": rec["ts"], "site_id": rec["site_id"], "camera_id": rec["camera_id"],
+          "id": alert_id, "ts": rec["ts"], "site_id": rec["site_id"], "camera_id": rec["camera_id"],
           "status": "threat", "level": level, "risk": rec["risk_local"],
           "reason": "local_risk", "objects": rec["objects"], "actions": rec["actions"],
           "zones": rec["zones"], "audio_flags": rec["audio_flags"], "audio_phrases": []
         });
-        write_one(&out)?;
+        write_one(&out, st).await?;
         st.last_quiet.insert(key.to_string(), now);
+
+        if !alert_id.is_empty() {
+            st.alerts.insert(alert_id.clone(), out.clone());
+
+            if level == "high" {
+                crate::stt::speak(&alert_id, &out);
+            }
+            crate::queue::enqueue(crate::queue::Job::Enrich { alert_id: alert_id.clone() }).await?;
+            crate::queue::enqueue(crate::queue::Job::NotifyOwner { alert_id: alert_id.clone() }).await?;
+            if level == "high" {
+                crate::queue::enqueue(crate::queue::Job::NotifyResponder { alert_id }).await?;
+            }
+        }
+        let _ = st.alert_events.send(out);
     } else if now.saturating_sub(last) >= 15 {
         let out = json!({
           "ts": rec["ts"], "site_id": rec["site_id"], "camera_id": rec["camera_id"],
           "status": "no_threat", "window_sec": 15
         });
-        write_one(&out)?;
+        write_one(&out, st).await?;
         st.last_quiet.insert(key.to_string(), now);
     }
     Ok(())
 }
 
-fn write_one(v: &serde_json::Value) -> anyhow::Result<()> {
-    let p = root().join(format!("{}_{}_{}.json",
+fn key_for(v: &serde_json::Value) -> String {
+    format!("{}_{}_{}.json",
         v["site_id"].as_str().unwrap_or("site"),
         v["camera_id"].as_str().unwrap_or("cam"),
-        v["ts"].as_str().unwrap_or("ts")));
-    fs::create_dir_all(p.parent().unwrap())?;
-    fs::write(p, serde_json::to_vec(v)?)?;
-    Ok(())
+        v["ts"].as_str().unwrap_or("ts"))
 }
 
-pub async fn latest_for(site: &str, cam: &str) -> anyhow::Result<serde_json::Value> {
-    let dir = root();
-    let mut latest: Option<(std::time::SystemTime, std::path::PathBuf)> = None;
-    if let Ok(read) = std::fs::read_dir(&dir) {
-        for e in read.flatten() {
-            let n = e.file_name().to_string_lossy().to_string();
-            if n.starts_with(&format!("{}_{}", site, cam)) {
-                let md = e.metadata().ok();
-                let mt = md.and_then(|m| m.modified().ok()).unwrap_or(std::time::SystemTime::UNIX_EPOCH);
-                if latest.as_ref().map(|(t,_)| mt> *t).unwrap_or(true) { latest = Some((mt, e.path())); }
-            }
-        }
-    }
-    if let Some((_, p)) = latest {
-        let b = fs::read(p)?; Ok(serde_json::from_slice(&b)?)
-    } else {
-        Ok(json!({}))
+async fn write_one(v: &serde_json::Value, st: &AppState) -> anyhow::Result<()> {
+    st.store.put(&key_for(v), serde_json::to_vec(v)?).await
+}
+
+pub async fn latest_for(site: &str, cam: &str, st: &AppState) -> anyhow::Result<serde_json::Value> {
+    let prefix = format!("{}_{}", site, cam);
+    let Some(key) = st.store.list_prefix(&prefix).await? else {
+        return Ok(json!({}));
+    };
+    match st.store.get(&key).await? {
+        Some(bytes) => Ok(serde_json::from_slice(&bytes)?),
+        None => Ok(json!({})),
     }
 }
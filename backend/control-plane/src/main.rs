@@ -1,28 +1,54 @@
 pub mod proto;
+mod alerts;
+mod auth;
+mod cns;
 mod grpc_client;
+mod jsonlog;
+mod metrics;
 mod policy;
+mod queue;
+mod store;
+mod stt;
 mod api;
 
-use axum::{Router, routing::{get, post}};
+use axum::{middleware, Router, routing::{get, post}};
 use tower_http::trace::TraceLayer;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
+    metrics::install();
+
+    let state = api::AppState::from_env().await?;
+
     // Bind address for HTTP (Axum)
     let bind = std::env::var("RUST_HTTP_BIND").unwrap_or_else(|_| "0.0.0.0:8080".to_string());
     let listener = tokio::net::TcpListener::bind(&bind).await?;
     println!("surveilens-control-plane HTTP listening on http://{bind}");
 
-    let app = Router::new()
+    let protected = Router::new()
         // Cameras
         .route("/api/cameras/:site/:cam/start", post(api::post_start))
         .route("/api/cameras/:site/:cam/stop",  post(api::post_stop))
         // Policy
         .route("/api/policy/compile", post(api::post_policy_compile))
+        // Live alert stream
+        .route("/api/stream/:site/:cam", get(api::get_stream))
+        .layer(middleware::from_fn(auth::require_ticket))
+        .with_state(state.clone());
+
+    let app = Router::new()
+        .merge(protected)
+        // Auth
+        .route("/api/login", post(auth::post_login))
+        // Observability
+        .route("/metrics", get(metrics::get_metrics))
         // Health
         .route("/health", get(api::get_health))
         .layer(TraceLayer::new_for_http());
 
+    tokio::spawn(queue::run_worker(state.clone()));
+    tokio::spawn(stt::run(state.clone()));
+
     axum::serve(listener, app).await?;
     Ok(())
 }
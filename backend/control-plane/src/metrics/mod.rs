@@ -0,0 +1,48 @@
+use std::sync::OnceLock;
+use std::time::Instant;
+
+use axum::http::StatusCode;
+use metrics_exporter_prometheus::{PrometheusBuilder, PrometheusHandle};
+
+static HANDLE: OnceLock<PrometheusHandle> = OnceLock::new();
+
+pub fn install() {
+    let handle = PrometheusBuilder::new()
+        .install_recorder()
+        .expect("install prometheus recorder");
+    HANDLE.set(handle).ok();
+}
+
+pub async fn get_metrics() -> Result<String, StatusCode> {
+    HANDLE
+        .get()
+        .map(|h| h.render())
+        .ok_or(StatusCode::SERVICE_UNAVAILABLE)
+}
+
+pub fn track_request(controller: &str, method: &str, outcome: &str) {
+    metrics::counter!(
+        "surveilens_requests_total",
+        "controller" => controller.to_string(),
+        "method" => method.to_string(),
+        "outcome" => outcome.to_string(),
+    )
+    .increment(1);
+}
+
+pub fn record_latency(target: &str, started: Instant) {
+    metrics::histogram!("surveilens_outbound_latency_seconds", "target" => target.to_string())
+        .record(started.elapsed().as_secs_f64());
+}
+
+pub fn camera_started() {
+    metrics::gauge!("surveilens_active_cameras").increment(1.0);
+}
+
+pub fn camera_stopped() {
+    metrics::gauge!("surveilens_active_cameras").decrement(1.0);
+}
+
+pub fn set_alert_count(n: f64) {
+    metrics::gauge!("surveilens_alerts_total").set(n);
+}
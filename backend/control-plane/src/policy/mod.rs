@@ -1,7 +1,14 @@
+use std::time::Instant;
+
 use anyhow::Result;
 
+use crate::metrics;
+
 pub async fn compile_and_push(site_id: &str, camera_id: &str, params_json: serde_json::Value) -> Result<()> {
     let json_params = serde_json::to_string(&params_json)?;
-    crate::grpc_client::send_setparams(site_id, camera_id, json_params).await?;
+    let started = Instant::now();
+    let res = crate::grpc_client::send_setparams(site_id, camera_id, json_params).await;
+    metrics::record_latency("grpc", started);
+    res?;
     Ok(())
 }
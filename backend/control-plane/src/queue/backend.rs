@@ -0,0 +1,75 @@
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use std::{fs, io};
+
+use async_trait::async_trait;
+
+use super::JobRecord;
+
+fn root() -> PathBuf { PathBuf::from("surveilens/backend/data/queue") }
+fn pending_dir() -> PathBuf { root().join("pending") }
+fn dead_letter_path() -> PathBuf { root().join("dead_letter.jsonl") }
+
+#[async_trait]
+pub trait QueueBackend: Send + Sync {
+    async fn push(&self, rec: JobRecord) -> anyhow::Result<()>;
+    async fn pop_due(&self) -> anyhow::Result<Option<JobRecord>>;
+    async fn dead_letter(&self, rec: JobRecord) -> anyhow::Result<()>;
+}
+
+// One JSON file per pending job under surveilens/backend/data/queue/pending, named
+// <next_attempt_at>_<rand> so the lexicographically-smallest file is always due next.
+pub struct FileQueue;
+
+impl FileQueue {
+    pub fn new() -> Self { Self }
+}
+
+#[async_trait]
+impl QueueBackend for FileQueue {
+    async fn push(&self, rec: JobRecord) -> anyhow::Result<()> {
+        fs::create_dir_all(pending_dir())?;
+        let name = format!("{:020}_{:06}.json", rec.next_attempt_at, fastrand::u32(0..1_000_000));
+        fs::write(pending_dir().join(name), serde_json::to_vec(&rec)?)?;
+        Ok(())
+    }
+
+    async fn pop_due(&self) -> anyhow::Result<Option<JobRecord>> {
+        fs::create_dir_all(pending_dir())?;
+        let mut earliest: Option<(String, PathBuf)> = None;
+        for entry in fs::read_dir(pending_dir())?.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if earliest.as_ref().map(|(best, _)| name < *best).unwrap_or(true) {
+                earliest = Some((name, entry.path()));
+            }
+        }
+        let Some((_, path)) = earliest else { return Ok(None) };
+        let rec: JobRecord = serde_json::from_slice(&fs::read(&path)?)?;
+        if rec.next_attempt_at > super::now() {
+            return Ok(None);
+        }
+        if let Err(e) = fs::remove_file(&path) {
+            if e.kind() == io::ErrorKind::NotFound {
+                return Ok(None);
+            }
+            return Err(e.into());
+        }
+        Ok(Some(rec))
+    }
+
+    async fn dead_letter(&self, rec: JobRecord) -> anyhow::Result<()> {
+        fs::create_dir_all(root())?;
+        let mut line = serde_json::to_vec(&rec)?;
+        line.push(b'\n');
+        let mut f = fs::OpenOptions::new().create(true).append(true).open(dead_letter_path())?;
+        f.write_all(&line)?;
+        Ok(())
+    }
+}
+
+static QUEUE: OnceLock<FileQueue> = OnceLock::new();
+
+pub fn default_queue() -> &'static FileQueue {
+    QUEUE.get_or_init(FileQueue::new)
+}
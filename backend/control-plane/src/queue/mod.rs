@@ -0,0 +1,90 @@
+mod backend;
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::api::AppState;
+pub use backend::{FileQueue, QueueBackend};
+
+const BASE_BACKOFF_SECS: u64 = 5;
+const MAX_BACKOFF_SECS: u64 = 300;
+const DEFAULT_MAX_ATTEMPTS: u32 = 8;
+const POLL_IDLE: Duration = Duration::from_secs(1);
+
+fn max_attempts() -> u32 {
+    std::env::var("SURVEILENS_QUEUE_MAX_ATTEMPTS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_MAX_ATTEMPTS)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Job {
+    Enrich { alert_id: String },
+    NotifyOwner { alert_id: String },
+    NotifyResponder { alert_id: String },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobRecord {
+    pub job: Job,
+    pub attempts: u32,
+    pub next_attempt_at: u64,
+}
+
+impl JobRecord {
+    fn new(job: Job) -> Self {
+        Self { job, attempts: 0, next_attempt_at: now() }
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+}
+
+// base * 2^attempts, capped, plus up to 25% jitter so retries don't thunder-herd after an outage.
+fn backoff_secs(attempts: u32) -> u64 {
+    let capped = BASE_BACKOFF_SECS.saturating_mul(1u64 << attempts.min(16)).min(MAX_BACKOFF_SECS);
+    capped + fastrand::u64(0..=capped / 4)
+}
+
+pub async fn enqueue(job: Job) -> anyhow::Result<()> {
+    backend::default_queue().push(JobRecord::new(job)).await
+}
+
+pub async fn run_worker(st: AppState) {
+    loop {
+        match backend::default_queue().pop_due().await {
+            Ok(Some(mut rec)) => {
+                if let Err(e) = execute(&rec.job, &st).await {
+                    rec.attempts += 1;
+                    if rec.attempts >= max_attempts() {
+                        tracing::error!("job {:?} dead-lettered after {} attempts: {e:#}", rec.job, rec.attempts);
+                        let _ = backend::default_queue().dead_letter(rec).await;
+                    } else {
+                        rec.next_attempt_at = now() + backoff_secs(rec.attempts);
+                        tracing::warn!("job {:?} failed (attempt {}), retrying: {e:#}", rec.job, rec.attempts);
+                        let _ = backend::default_queue().push(rec).await;
+                    }
+                }
+            }
+            Ok(None) => tokio::time::sleep(POLL_IDLE).await,
+            Err(e) => {
+                tracing::error!("queue pop failed: {e:#}");
+                tokio::time::sleep(POLL_IDLE).await;
+            }
+        }
+    }
+}
+
+async fn execute(job: &Job, st: &AppState) -> anyhow::Result<()> {
+    match job {
+        Job::Enrich { alert_id } => {
+            let alert = st.alerts.get(alert_id).map(|x| x.value().clone()).unwrap_or_default();
+            crate::cns::enrich(alert, st).await
+        }
+        Job::NotifyOwner { alert_id } => crate::alerts::notify_owner(alert_id, st).await,
+        Job::NotifyResponder { alert_id } => crate::alerts::notify_responder(alert_id, st).await,
+    }
+}
@@ -0,0 +1,43 @@
+use async_trait::async_trait;
+use std::{fs, io, path::PathBuf, time::SystemTime};
+
+use super::Store;
+
+fn root() -> PathBuf { PathBuf::from("surveilens/backend/data/jsonlogs") }
+
+#[derive(Default)]
+pub struct FileStore;
+
+#[async_trait]
+impl Store for FileStore {
+    async fn put(&self, key: &str, bytes: Vec<u8>) -> anyhow::Result<()> {
+        let p = root().join(key);
+        fs::create_dir_all(p.parent().unwrap())?;
+        fs::write(p, bytes)?;
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> anyhow::Result<Option<Vec<u8>>> {
+        match fs::read(root().join(key)) {
+            Ok(bytes) => Ok(Some(bytes)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn list_prefix(&self, prefix: &str) -> anyhow::Result<Option<String>> {
+        let mut latest: Option<(SystemTime, String)> = None;
+        if let Ok(read) = fs::read_dir(root()) {
+            for e in read.flatten() {
+                let name = e.file_name().to_string_lossy().to_string();
+                if name.starts_with(prefix) {
+                    let modified = e.metadata().ok().and_then(|m| m.modified().ok()).unwrap_or(SystemTime::UNIX_EPOCH);
+                    if latest.as_ref().map(|(t, _)| modified > *t).unwrap_or(true) {
+                        latest = Some((modified, name));
+                    }
+                }
+            }
+        }
+        Ok(latest.map(|(_, name)| name))
+    }
+}
@@ -0,0 +1,26 @@
+mod file;
+mod s3;
+
+pub use file::FileStore;
+pub use s3::S3Store;
+
+use async_trait::async_trait;
+use std::sync::Arc;
+
+#[async_trait]
+pub trait Store: Send + Sync {
+    async fn put(&self, key: &str, bytes: Vec<u8>) -> anyhow::Result<()>;
+    async fn get(&self, key: &str) -> anyhow::Result<Option<Vec<u8>>>;
+    // Returns the most-recently-written key starting with `prefix`, if any.
+    async fn list_prefix(&self, prefix: &str) -> anyhow::Result<Option<String>>;
+}
+
+pub type DynStore = Arc<dyn Store>;
+
+pub async fn from_env() -> anyhow::Result<DynStore> {
+    match std::env::var("SURVEILENS_STORE").as_deref() {
+        Ok("s3") => Ok(Arc::new(S3Store::from_env().await?)),
+        Ok("file") | Err(_) => Ok(Arc::new(FileStore::default())),
+        Ok(other) => Err(anyhow::anyhow!("unknown SURVEILENS_STORE backend: {other}")),
+    }
+}
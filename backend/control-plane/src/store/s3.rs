@@ -0,0 +1,66 @@
+use async_trait::async_trait;
+use aws_sdk_s3::primitives::ByteStream;
+use aws_sdk_s3::Client;
+
+use super::Store;
+
+pub struct S3Store {
+    client: Client,
+    bucket: String,
+}
+
+impl S3Store {
+    pub async fn from_env() -> anyhow::Result<Self> {
+        let bucket = std::env::var("SURVEILENS_S3_BUCKET")
+            .map_err(|_| anyhow::anyhow!("SURVEILENS_S3_BUCKET must be set when SURVEILENS_STORE=s3"))?;
+        let config = aws_config::load_from_env().await;
+        Ok(Self { client: Client::new(&config), bucket })
+    }
+}
+
+#[async_trait]
+impl Store for S3Store {
+    async fn put(&self, key: &str, bytes: Vec<u8>) -> anyhow::Result<()> {
+        self.client
+            .put_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .body(ByteStream::from(bytes))
+            .send()
+            .await?;
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> anyhow::Result<Option<Vec<u8>>> {
+        match self.client.get_object().bucket(&self.bucket).key(key).send().await {
+            Ok(out) => Ok(Some(out.body.collect().await?.into_bytes().to_vec())),
+            Err(aws_sdk_s3::error::SdkError::ServiceError(e)) if e.err().is_no_such_key() => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn list_prefix(&self, prefix: &str) -> anyhow::Result<Option<String>> {
+        let mut newest: Option<(Option<aws_sdk_s3::primitives::DateTime>, String)> = None;
+        let mut continuation_token = None;
+        loop {
+            let mut req = self.client.list_objects_v2().bucket(&self.bucket).prefix(prefix);
+            if let Some(token) = continuation_token.take() {
+                req = req.continuation_token(token);
+            }
+            let out = req.send().await?;
+            for o in out.contents() {
+                let Some(key) = o.key() else { continue };
+                let modified = o.last_modified().copied();
+                if newest.as_ref().map(|(m, _)| modified > *m).unwrap_or(true) {
+                    newest = Some((modified, key.to_string()));
+                }
+            }
+            if out.is_truncated().unwrap_or(false) {
+                continuation_token = out.next_continuation_token().map(|s| s.to_string());
+            } else {
+                break;
+            }
+        }
+        Ok(newest.map(|(_, key)| key))
+    }
+}
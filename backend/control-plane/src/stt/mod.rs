@@ -1,15 +1,170 @@
 use crate::api::AppState;
-use tokio_tungstenite::connect_async;
 use futures::{SinkExt, StreamExt};
-use serde_json::json;
-
-pub async fn run(_st: AppState) {
-    if let Ok(url) = std::env::var("ELEVENLABS_WS_URL") {
-        if let Ok((mut ws, _)) = connect_async(url).await {
-            let _ = ws.send(tokio_tungstenite::tungstenite::Message::Text(
-                json!({"hello":"world"}).to_string()
-            )).await;
-            while let Some(_msg) = ws.next().await {}
+use serde_json::{json, Value};
+use std::sync::OnceLock;
+use std::time::Duration;
+use tokio::net::TcpStream;
+use tokio::sync::mpsc;
+use tokio_tungstenite::{connect_async, tungstenite::Message, MaybeTlsStream, WebSocketStream};
+
+const RECONNECT_BASE: Duration = Duration::from_secs(1);
+const RECONNECT_MAX: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SessionState {
+    Idle,
+    Speaking,
+    Listening,
+    Escalate,
+}
+
+struct SpeakRequest {
+    alert_id: String,
+    site: String,
+    cam: String,
+    text: String,
+}
+
+static SPEAK_TX: OnceLock<mpsc::UnboundedSender<SpeakRequest>> = OnceLock::new();
+
+fn enabled() -> bool {
+    std::env::var("SURVEILENS_AUDIO_ENABLED")
+        .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+        .unwrap_or(false)
+}
+
+fn warning_text(alert: &Value) -> String {
+    let zones = alert["zones"]
+        .as_array()
+        .map(|a| a.iter().filter_map(|z| z.as_str()).collect::<Vec<_>>().join(", "))
+        .unwrap_or_default();
+    let actions = alert["actions"]
+        .as_array()
+        .map(|a| a.iter().filter_map(|o| o["name"].as_str()).collect::<Vec<_>>().join(", "))
+        .unwrap_or_default();
+
+    if zones.is_empty() {
+        "Attention: this area is under surveillance. Please leave the premises now.".to_string()
+    } else if actions.is_empty() {
+        format!("Attention in {zones}: this area is under surveillance. Please leave the premises now.")
+    } else {
+        format!("Attention in {zones}: {actions} detected. This area is under surveillance, please leave the premises now.")
+    }
+}
+
+pub fn speak(alert_id: &str, alert: &Value) {
+    if !enabled() {
+        return;
+    }
+    let Some(tx) = SPEAK_TX.get() else { return };
+    let _ = tx.send(SpeakRequest {
+        alert_id: alert_id.to_string(),
+        site: alert["site_id"].as_str().unwrap_or_default().to_string(),
+        cam: alert["camera_id"].as_str().unwrap_or_default().to_string(),
+        text: warning_text(alert),
+    });
+}
+
+pub async fn run(st: AppState) {
+    if !enabled() {
+        return;
+    }
+    let Ok(url) = std::env::var("ELEVENLABS_WS_URL") else {
+        return;
+    };
+
+    let (tx, mut rx) = mpsc::unbounded_channel::<SpeakRequest>();
+    SPEAK_TX.set(tx).ok();
+
+    let mut backoff = RECONNECT_BASE;
+    loop {
+        match connect_async(&url).await {
+            Ok((ws, _)) => {
+                backoff = RECONNECT_BASE;
+                run_session(ws, &mut rx, &st).await;
+            }
+            Err(e) => {
+                tracing::warn!("elevenlabs ws connect failed, retrying in {backoff:?}: {e:#}");
+            }
+        }
+        tokio::time::sleep(backoff).await;
+        backoff = (backoff * 2).min(RECONNECT_MAX);
+    }
+}
+
+async fn run_session(
+    ws: WebSocketStream<MaybeTlsStream<TcpStream>>,
+    rx: &mut mpsc::UnboundedReceiver<SpeakRequest>,
+    st: &AppState,
+) {
+    let (mut write, mut read) = ws.split();
+    let mut state = SessionState::Idle;
+    let mut speaking: Option<SpeakRequest> = None;
+
+    loop {
+        tokio::select! {
+            req = rx.recv() => {
+                let Some(req) = req else { return };
+                state = SessionState::Speaking;
+                tracing::debug!("alert {}: {:?}", req.alert_id, state);
+                let frame = json!({ "type": "speak", "alert_id": req.alert_id, "text": req.text });
+                if write.send(Message::Text(frame.to_string())).await.is_err() {
+                    return;
+                }
+                speaking = Some(req);
+            }
+            msg = read.next() => {
+                match msg {
+                    Some(Ok(Message::Binary(audio))) => {
+                        let Some(req) = speaking.as_ref() else { continue };
+                        if let Err(e) = crate::grpc_client::stream_audio(&req.site, &req.cam, audio).await {
+                            tracing::warn!("alert {}: audio relay to {}/{} failed: {e:#}", req.alert_id, req.site, req.cam);
+                        }
+                    }
+                    Some(Ok(Message::Text(text))) => {
+                        let Ok(reply) = serde_json::from_str::<Value>(&text) else { continue };
+
+                        if reply["event"].as_str() == Some("speech_complete") {
+                            speaking = None;
+                            state = SessionState::Listening;
+                            tracing::debug!("{:?}", state);
+                            continue;
+                        }
+
+                        let Some(phrase) = reply["phrase"].as_str() else { continue };
+                        let alert_id = reply["alert_id"].as_str().unwrap_or_default();
+                        record_phrase(st, alert_id, phrase);
+
+                        state = if is_escalation_phrase(phrase) {
+                            let _ = crate::alerts::notify_responder(alert_id, st).await;
+                            SessionState::Escalate
+                        } else {
+                            SessionState::Listening
+                        };
+                        tracing::debug!("alert {alert_id}: {:?}", state);
+                    }
+                    Some(Ok(Message::Close(_))) | None => return,
+                    Some(Err(_)) => return,
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+fn is_escalation_phrase(phrase: &str) -> bool {
+    let lower = phrase.to_lowercase();
+    lower.contains("help") || lower.contains("weapon") || lower.contains("won't leave")
+}
+
+fn record_phrase(st: &AppState, alert_id: &str, phrase: &str) {
+    if alert_id.is_empty() {
+        return;
+    }
+    if let Some(mut entry) = st.alerts.get_mut(alert_id) {
+        match entry.value_mut()["audio_phrases"].as_array_mut() {
+            Some(phrases) => phrases.push(json!(phrase)),
+            None => entry.value_mut()["audio_phrases"] = json!([phrase]),
         }
     }
 }